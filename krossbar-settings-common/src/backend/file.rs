@@ -0,0 +1,138 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::mpsc::Receiver,
+};
+
+use serde_json::{Map, Value};
+
+use super::SettingsBackend;
+use crate::{
+    format::Format,
+    lenient::strip_comments_and_trailing_commas,
+    watch::{watch_path, ChangeEvent},
+};
+
+/// Stores settings as a single file, in whichever format its extension selects
+/// (`.json`, `.ron`, `.yaml`/`.yml`, `.toml`)
+pub struct FileBackend {
+    settings_file: File,
+    path: PathBuf,
+    format: Format,
+    /// Tolerate `//`/`/* */` comments and trailing commas when reading JSON
+    lenient: bool,
+}
+
+impl FileBackend {
+    /// Open settings file at **path**, creating it with an empty object if it doesn't
+    /// exist. The format is chosen from the path's extension.
+    pub fn new(path: &Path) -> crate::Result<Self> {
+        let format = Format::from_path(path)?;
+
+        let settings_file = if !Path::new(path).exists() {
+            let mut file =
+                File::create_new(path).map_err(|e| crate::Error::IoError(e.to_string()))?;
+
+            file.write_all(&format.serialize(&Value::Object(Map::new()))?)
+                .map_err(|e| crate::Error::IoError(e.to_string()))?;
+
+            file
+        } else {
+            OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(path)
+                .map_err(|e| crate::Error::IoError(e.to_string()))?
+        };
+
+        Ok(Self {
+            settings_file,
+            path: path.to_owned(),
+            format,
+            lenient: false,
+        })
+    }
+
+    /// Tolerate hand-edited quirks when reading JSON: C-style `//` and `/* */` comments
+    /// and trailing commas in objects and arrays. Comments are dropped on write-back,
+    /// since strict JSON has nowhere to keep them. No-op for other formats.
+    pub fn with_lenient_parsing(mut self) -> Self {
+        self.lenient = true;
+        self
+    }
+}
+
+impl SettingsBackend for FileBackend {
+    fn load(&mut self) -> crate::Result<Map<String, Value>> {
+        self.settings_file
+            .seek(SeekFrom::Start(0))
+            .map_err(|e| crate::Error::IoError(e.to_string()))?;
+
+        let mut data = Vec::new();
+        self.settings_file
+            .read_to_end(&mut data)
+            .map_err(|e| crate::Error::IoError(e.to_string()))?;
+
+        if self.lenient && self.format == Format::Json {
+            data = strip_comments_and_trailing_commas(&data);
+        }
+
+        match self.format.parse(&data)? {
+            Value::Object(map) => Ok(map),
+            _ => Err(crate::Error::Corrupted(
+                "Root settings elemetn is not an Object".into(),
+            )),
+        }
+    }
+
+    /// Write **map** out crash-safely: serialize to a sibling temp file in the same
+    /// directory, `fsync` it, then atomically rename it over the settings file, so a
+    /// crash or power loss mid-write never leaves a truncated or corrupted file behind.
+    fn store(&mut self, map: &Map<String, Value>) -> crate::Result<()> {
+        let data_to_write = self.format.serialize(&Value::Object(map.clone()))?;
+
+        let temp_path = sibling_temp_path(&self.path);
+
+        let mut temp_file =
+            File::create(&temp_path).map_err(|e| crate::Error::IoError(e.to_string()))?;
+
+        temp_file
+            .write_all(&data_to_write)
+            .map_err(|e| crate::Error::IoError(e.to_string()))?;
+
+        temp_file
+            .sync_all()
+            .map_err(|e| crate::Error::IoError(e.to_string()))?;
+
+        drop(temp_file);
+
+        std::fs::rename(&temp_path, &self.path)
+            .map_err(|e| crate::Error::IoError(e.to_string()))?;
+
+        // The rename replaced the file our handle was pointing at; reopen so
+        // subsequent loads see the new content through the same `FileBackend`.
+        self.settings_file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&self.path)
+            .map_err(|e| crate::Error::IoError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    fn watch(&self) -> crate::Result<Receiver<ChangeEvent>> {
+        watch_path(self.path.clone(), self.format, self.lenient)
+    }
+}
+
+/// Build the path of a temp file next to **path**, in the same directory so the
+/// follow-up rename stays on one filesystem and is atomic
+fn sibling_temp_path(path: &Path) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("settings");
+
+    path.with_file_name(format!(".{file_name}.tmp"))
+}
@@ -0,0 +1,169 @@
+use std::path::PathBuf;
+
+use serde_json::{Map, Value};
+
+use super::{FileBackend, SettingsBackend};
+
+/// Merges an ordered list of settings files (each in whichever format its extension
+/// selects), least to most specific, into a single
+/// view: e.g. a read-only defaults file shipped with the app followed by one or more
+/// user override files.
+///
+/// Reads return the deep-merged result of all layers. Writes only ever touch the
+/// topmost (last) layer, and only store keys whose value actually differs from what
+/// the lower layers already provide, so defaults never get copied wholesale into the
+/// user's file.
+pub struct LayeredBackend {
+    /// Ordered least to most specific; the last layer is the one writes go to
+    layers: Vec<FileBackend>,
+}
+
+impl LayeredBackend {
+    /// Open a layered backend over **paths**, ordered from least to most specific
+    pub fn new(paths: &[PathBuf]) -> crate::Result<Self> {
+        if paths.is_empty() {
+            return Err(crate::Error::Backend(
+                "layered settings require at least one file".into(),
+            ));
+        }
+
+        let layers = paths
+            .iter()
+            .map(|path| FileBackend::new(path))
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        Ok(Self { layers })
+    }
+}
+
+impl SettingsBackend for LayeredBackend {
+    fn load(&mut self) -> crate::Result<Map<String, Value>> {
+        let mut merged = Map::new();
+
+        for layer in self.layers.iter_mut() {
+            merged = merge_non_null(merged, layer.load()?);
+        }
+
+        Ok(merged)
+    }
+
+    fn store(&mut self, map: &Map<String, Value>) -> crate::Result<()> {
+        let (base_layers, top_layer) = self.layers.split_at_mut(self.layers.len() - 1);
+
+        let mut base = Map::new();
+        for layer in base_layers.iter_mut() {
+            base = merge_non_null(base, layer.load()?);
+        }
+
+        // Only keep keys (recursively) that actually differ from what the lower layers
+        // already give us, so unrelated sibling keys under the same object aren't
+        // copied into the user layer just because one of their neighbors changed
+        let overrides = diff_from_base(&base, map);
+
+        top_layer[0].store(&overrides)
+    }
+}
+
+/// Recursively overlay **overlay** onto **base**: objects merge key-by-key, scalars and
+/// arrays replace wholesale, and a JSON `null` in the overlay means "fall back to the
+/// lower layer" rather than overwriting it.
+fn merge_non_null(mut base: Map<String, Value>, overlay: Map<String, Value>) -> Map<String, Value> {
+    for (key, overlay_value) in overlay {
+        if overlay_value.is_null() {
+            continue;
+        }
+
+        match (base.remove(&key), overlay_value) {
+            (Some(Value::Object(base_obj)), Value::Object(overlay_obj)) => {
+                base.insert(key, Value::Object(merge_non_null(base_obj, overlay_obj)));
+            }
+            (_, overlay_value) => {
+                base.insert(key, overlay_value);
+            }
+        }
+    }
+
+    base
+}
+
+/// Compute what **incoming** adds on top of **base**, recursing into nested objects so
+/// that only genuinely-changed leaves are returned instead of whole parent objects.
+/// A key absent from **incoming** (e.g. cleared) is simply left out, same as today.
+fn diff_from_base(base: &Map<String, Value>, incoming: &Map<String, Value>) -> Map<String, Value> {
+    let mut overrides = Map::new();
+
+    for (key, incoming_value) in incoming {
+        match (base.get(key), incoming_value) {
+            (Some(Value::Object(base_obj)), Value::Object(incoming_obj)) => {
+                let nested = diff_from_base(base_obj, incoming_obj);
+
+                if !nested.is_empty() {
+                    overrides.insert(key.clone(), Value::Object(nested));
+                }
+            }
+            (Some(base_value), _) if base_value == incoming_value => {}
+            _ => {
+                overrides.insert(key.clone(), incoming_value.clone());
+            }
+        }
+    }
+
+    overrides
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn merge_overlays_object_values_key_by_key() {
+        let base = json!({"window": {"width": 800, "height": 600}}).as_object().unwrap().clone();
+        let overlay = json!({"window": {"height": 700}}).as_object().unwrap().clone();
+
+        let merged = merge_non_null(base, overlay);
+
+        assert_eq!(Value::Object(merged), json!({"window": {"width": 800, "height": 700}}));
+    }
+
+    #[test]
+    fn merge_replaces_scalars_and_arrays_wholesale() {
+        let base = json!({"tags": ["a", "b"], "volume": 1}).as_object().unwrap().clone();
+        let overlay = json!({"tags": ["c"], "volume": 2}).as_object().unwrap().clone();
+
+        let merged = merge_non_null(base, overlay);
+
+        assert_eq!(Value::Object(merged), json!({"tags": ["c"], "volume": 2}));
+    }
+
+    #[test]
+    fn merge_null_in_overlay_falls_back_to_base() {
+        let base = json!({"volume": 1}).as_object().unwrap().clone();
+        let overlay = json!({"volume": null}).as_object().unwrap().clone();
+
+        let merged = merge_non_null(base, overlay);
+
+        assert_eq!(Value::Object(merged), json!({"volume": 1}));
+    }
+
+    #[test]
+    fn diff_omits_unchanged_nested_siblings() {
+        let base = json!({"window": {"width": 800}}).as_object().unwrap().clone();
+        let incoming = json!({"window": {"width": 800, "height": 600}})
+            .as_object()
+            .unwrap()
+            .clone();
+
+        let overrides = diff_from_base(&base, &incoming);
+
+        assert_eq!(Value::Object(overrides), json!({"window": {"height": 600}}));
+    }
+
+    #[test]
+    fn diff_is_empty_when_nothing_changed() {
+        let base = json!({"volume": 1}).as_object().unwrap().clone();
+
+        assert!(diff_from_base(&base, &base).is_empty());
+    }
+}
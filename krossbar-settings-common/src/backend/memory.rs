@@ -0,0 +1,34 @@
+use serde_json::{Map, Value};
+
+use super::SettingsBackend;
+
+/// Keeps settings purely in memory, with no persistence.
+///
+/// Useful for tests and for ephemeral settings that shouldn't outlive the process.
+#[derive(Default)]
+pub struct MemoryBackend {
+    map: Map<String, Value>,
+}
+
+impl MemoryBackend {
+    /// Create an empty in-memory backend
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an in-memory backend pre-populated with **map**
+    pub fn with_values(map: Map<String, Value>) -> Self {
+        Self { map }
+    }
+}
+
+impl SettingsBackend for MemoryBackend {
+    fn load(&mut self) -> crate::Result<Map<String, Value>> {
+        Ok(self.map.clone())
+    }
+
+    fn store(&mut self, map: &Map<String, Value>) -> crate::Result<()> {
+        self.map = map.clone();
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+mod file;
+mod layered;
+mod memory;
+mod sqlite;
+
+pub use file::FileBackend;
+pub use layered::LayeredBackend;
+pub use memory::MemoryBackend;
+pub use sqlite::SqliteBackend;
+
+use std::sync::mpsc::Receiver;
+
+use serde_json::{Map, Value};
+
+use crate::watch::ChangeEvent;
+
+/// Persistence strategy used by [`crate::Settings`].
+///
+/// Implementations own the details of where settings live (a file, a database, memory)
+/// and how they're loaded and written back; `Settings` only ever sees a single JSON object.
+pub trait SettingsBackend {
+    /// Load the full settings map from the backing store
+    fn load(&mut self) -> crate::Result<Map<String, Value>>;
+
+    /// Persist the full settings map to the backing store
+    fn store(&mut self, map: &Map<String, Value>) -> crate::Result<()>;
+
+    /// Subscribe to changes made to the backing store, whether by other processes or
+    /// through this backend's own `store`.
+    ///
+    /// Not every backend can observe external changes; the default implementation
+    /// reports that watching isn't supported.
+    fn watch(&self) -> crate::Result<Receiver<ChangeEvent>> {
+        Err(crate::Error::Backend(
+            "this backend doesn't support watching".into(),
+        ))
+    }
+}
@@ -0,0 +1,182 @@
+use std::path::Path;
+
+use rusqlite::Connection;
+use serde_json::{Map, Value};
+
+use super::SettingsBackend;
+
+/// Stores each setting as a `(key, value)` row in a SQLite table, instead of rewriting
+/// a whole file on every change.
+///
+/// Only keys that actually changed are rewritten on [`SqliteBackend::store`], which keeps
+/// write amplification down for settings sets with many keys.
+pub struct SqliteBackend {
+    connection: Connection,
+}
+
+impl SqliteBackend {
+    /// Open (creating if necessary) a SQLite-backed settings store at **path**
+    pub fn new(path: &Path) -> crate::Result<Self> {
+        let connection =
+            Connection::open(path).map_err(|e| crate::Error::Backend(e.to_string()))?;
+
+        connection
+            .execute(
+                "CREATE TABLE IF NOT EXISTS settings (key TEXT PRIMARY KEY, value TEXT NOT NULL)",
+                [],
+            )
+            .map_err(|e| crate::Error::Backend(e.to_string()))?;
+
+        Ok(Self { connection })
+    }
+}
+
+impl SettingsBackend for SqliteBackend {
+    fn load(&mut self) -> crate::Result<Map<String, Value>> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT key, value FROM settings")
+            .map_err(|e| crate::Error::Backend(e.to_string()))?;
+
+        let rows = statement
+            .query_map([], |row| {
+                let key: String = row.get(0)?;
+                let value: String = row.get(1)?;
+                Ok((key, value))
+            })
+            .map_err(|e| crate::Error::Backend(e.to_string()))?;
+
+        let mut map = Map::new();
+        for row in rows {
+            let (key, value) = row.map_err(|e| crate::Error::Backend(e.to_string()))?;
+            let value: Value =
+                serde_json::from_str(&value).map_err(|e| crate::Error::Corrupted(e.to_string()))?;
+            map.insert(key, value);
+        }
+
+        Ok(map)
+    }
+
+    fn store(&mut self, map: &Map<String, Value>) -> crate::Result<()> {
+        let existing = self.load()?;
+
+        let transaction = self
+            .connection
+            .transaction()
+            .map_err(|e| crate::Error::Backend(e.to_string()))?;
+
+        for (key, value) in map {
+            if existing.get(key) == Some(value) {
+                continue;
+            }
+
+            let serialized =
+                serde_json::to_string(value).map_err(|e| crate::Error::Type(e.to_string()))?;
+
+            transaction
+                .execute(
+                    "INSERT INTO settings (key, value) VALUES (?1, ?2)
+                     ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+                    rusqlite::params![key, serialized],
+                )
+                .map_err(|e| crate::Error::Backend(e.to_string()))?;
+        }
+
+        for key in existing.keys() {
+            if !map.contains_key(key) {
+                transaction
+                    .execute("DELETE FROM settings WHERE key = ?1", rusqlite::params![key])
+                    .map_err(|e| crate::Error::Backend(e.to_string()))?;
+            }
+        }
+
+        transaction
+            .commit()
+            .map_err(|e| crate::Error::Backend(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn backend() -> SqliteBackend {
+        SqliteBackend::new(Path::new(":memory:")).unwrap()
+    }
+
+    fn row_count(backend: &SqliteBackend) -> i64 {
+        backend
+            .connection
+            .query_row("SELECT COUNT(*) FROM settings", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn store_then_load_round_trips() {
+        let mut backend = backend();
+        let mut map = Map::new();
+        map.insert("volume".into(), json!(5));
+
+        backend.store(&map).unwrap();
+
+        assert_eq!(backend.load().unwrap(), map);
+    }
+
+    #[test]
+    fn store_updates_an_existing_key_in_place() {
+        let mut backend = backend();
+        let mut map = Map::new();
+        map.insert("volume".into(), json!(5));
+        backend.store(&map).unwrap();
+
+        map.insert("volume".into(), json!(7));
+        backend.store(&map).unwrap();
+
+        assert_eq!(backend.load().unwrap(), map);
+        assert_eq!(row_count(&backend), 1);
+    }
+
+    #[test]
+    fn store_deletes_a_removed_key() {
+        let mut backend = backend();
+        let mut map = Map::new();
+        map.insert("volume".into(), json!(5));
+        map.insert("brightness".into(), json!(1));
+        backend.store(&map).unwrap();
+
+        map.remove("brightness");
+        backend.store(&map).unwrap();
+
+        assert_eq!(backend.load().unwrap(), map);
+        assert_eq!(row_count(&backend), 1);
+    }
+
+    #[test]
+    fn store_leaves_unchanged_keys_alone() {
+        let mut backend = backend();
+        let mut map = Map::new();
+        map.insert("volume".into(), json!(5));
+        map.insert("brightness".into(), json!(1));
+        backend.store(&map).unwrap();
+
+        let changes_before: i64 = backend
+            .connection
+            .query_row("SELECT total_changes()", [], |row| row.get(0))
+            .unwrap();
+
+        map.insert("brightness".into(), json!(2));
+        backend.store(&map).unwrap();
+
+        let changes_after: i64 = backend
+            .connection
+            .query_row("SELECT total_changes()", [], |row| row.get(0))
+            .unwrap();
+
+        assert_eq!(backend.load().unwrap(), map);
+        assert_eq!(row_count(&backend), 2);
+        // Only "brightness" was written; "volume" was left untouched
+        assert_eq!(changes_after - changes_before, 1);
+    }
+}
@@ -0,0 +1,27 @@
+use thiserror::Error;
+
+/// Crate-wide result type
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Settings operation error
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Underlying filesystem operation failed
+    #[error("Settings IO error: {0}")]
+    IoError(String),
+    /// Stored settings content can't be parsed in its format, or its root isn't an object
+    #[error("Settings file is corrupted: {0}")]
+    Corrupted(String),
+    /// Value couldn't be (de)serialized into the requested type
+    #[error("Settings value type error: {0}")]
+    Type(String),
+    /// No value found for the requested key
+    #[error("Settings value not found")]
+    NotFound,
+    /// Storage backend failed to load or persist settings
+    #[error("Settings backend error: {0}")]
+    Backend(String),
+    /// Settings file's extension doesn't map to a known serialization format
+    #[error("Unknown settings file extension: {0}")]
+    UnknownExtension(String),
+}
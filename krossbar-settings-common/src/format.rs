@@ -0,0 +1,189 @@
+use std::path::Path;
+
+use serde_json::Value;
+
+/// On-disk serialization format for a settings file, chosen by its extension.
+///
+/// The in-memory representation is always `serde_json::Value`, so every format just
+/// needs to parse into and serialize back out of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Format {
+    Json,
+    Ron,
+    Yaml,
+    Toml,
+}
+
+impl Format {
+    /// Infer the format from **path**'s extension. A missing extension falls back to
+    /// JSON, matching the format this crate always used before per-extension dispatch
+    /// existed, so existing extension-less settings files keep opening the same way.
+    /// An extension that's present but unrecognized is still an error.
+    pub(crate) fn from_path(path: &Path) -> crate::Result<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            None => Ok(Format::Json),
+            Some("json") => Ok(Format::Json),
+            Some("ron") => Ok(Format::Ron),
+            Some("yaml") | Some("yml") => Ok(Format::Yaml),
+            Some("toml") => Ok(Format::Toml),
+            Some(other) => Err(crate::Error::UnknownExtension(other.to_owned())),
+        }
+    }
+
+    pub(crate) fn parse(self, data: &[u8]) -> crate::Result<Value> {
+        match self {
+            Format::Json => {
+                serde_json::from_slice(data).map_err(|e| crate::Error::Corrupted(e.to_string()))
+            }
+            Format::Ron => {
+                ron::de::from_bytes(data).map_err(|e| crate::Error::Corrupted(e.to_string()))
+            }
+            Format::Yaml => {
+                serde_yaml::from_slice(data).map_err(|e| crate::Error::Corrupted(e.to_string()))
+            }
+            Format::Toml => {
+                let text =
+                    std::str::from_utf8(data).map_err(|e| crate::Error::Corrupted(e.to_string()))?;
+
+                toml::from_str(text).map_err(|e| crate::Error::Corrupted(e.to_string()))
+            }
+        }
+    }
+
+    pub(crate) fn serialize(self, value: &Value) -> crate::Result<Vec<u8>> {
+        match self {
+            Format::Json => serde_json::to_vec_pretty(value)
+                .map_err(|e| crate::Error::Type(e.to_string())),
+            Format::Ron => ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+                .map(String::into_bytes)
+                .map_err(|e| crate::Error::Type(e.to_string())),
+            Format::Yaml => serde_yaml::to_string(value)
+                .map(String::into_bytes)
+                .map_err(|e| crate::Error::Type(e.to_string())),
+            Format::Toml => {
+                if contains_null(value) {
+                    return Err(crate::Error::Type(
+                        "TOML has no representation for a JSON null value".into(),
+                    ));
+                }
+
+                toml::to_string_pretty(&scalars_before_tables(value))
+                    .map(String::into_bytes)
+                    .map_err(|e| crate::Error::Type(e.to_string()))
+            }
+        }
+    }
+}
+
+/// `toml` requires every scalar key in a table to be written before any table key
+/// (it errors with `ValueAfterTable` otherwise), but `serde_json::Map` preserves
+/// whatever insertion order the caller happened to use. Reorder each object,
+/// recursively, so scalar/array values come first and nested objects come last.
+fn scalars_before_tables(value: &Value) -> Value {
+    let Value::Object(map) = value else {
+        return value.clone();
+    };
+
+    let mut scalars = serde_json::Map::new();
+    let mut tables = serde_json::Map::new();
+
+    for (key, value) in map {
+        let value = scalars_before_tables(value);
+
+        if value.is_object() {
+            tables.insert(key.clone(), value);
+        } else {
+            scalars.insert(key.clone(), value);
+        }
+    }
+
+    scalars.extend(tables);
+    Value::Object(scalars)
+}
+
+/// Whether **value** contains a JSON `null` anywhere, which TOML cannot represent
+fn contains_null(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Array(items) => items.iter().any(contains_null),
+        Value::Object(map) => map.values().any(contains_null),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn from_path_dispatches_on_extension() {
+        assert_eq!(Format::from_path(Path::new("a.json")).unwrap(), Format::Json);
+        assert_eq!(Format::from_path(Path::new("a.ron")).unwrap(), Format::Ron);
+        assert_eq!(Format::from_path(Path::new("a.yaml")).unwrap(), Format::Yaml);
+        assert_eq!(Format::from_path(Path::new("a.yml")).unwrap(), Format::Yaml);
+        assert_eq!(Format::from_path(Path::new("a.toml")).unwrap(), Format::Toml);
+    }
+
+    #[test]
+    fn from_path_falls_back_to_json_without_an_extension() {
+        assert_eq!(Format::from_path(Path::new("settings")).unwrap(), Format::Json);
+    }
+
+    #[test]
+    fn from_path_rejects_an_unrecognized_extension() {
+        assert!(matches!(
+            Format::from_path(Path::new("a.txt")),
+            Err(crate::Error::UnknownExtension(ext)) if ext == "txt"
+        ));
+    }
+
+    #[test]
+    fn json_round_trips_nested_values() {
+        let value = json!({"window": {"width": 800, "height": 600}, "volume": 5});
+
+        let data = Format::Json.serialize(&value).unwrap();
+
+        assert_eq!(Format::Json.parse(&data).unwrap(), value);
+    }
+
+    #[test]
+    fn ron_round_trips_nested_values() {
+        let value = json!({"window": {"width": 800, "height": 600}, "volume": 5});
+
+        let data = Format::Ron.serialize(&value).unwrap();
+
+        assert_eq!(Format::Ron.parse(&data).unwrap(), value);
+    }
+
+    #[test]
+    fn yaml_round_trips_nested_values() {
+        let value = json!({"window": {"width": 800, "height": 600}, "volume": 5});
+
+        let data = Format::Yaml.serialize(&value).unwrap();
+
+        assert_eq!(Format::Yaml.parse(&data).unwrap(), value);
+    }
+
+    #[test]
+    fn toml_round_trips_nested_values_regardless_of_key_order() {
+        // "volume" (a scalar) is declared after "window" (a table): naive serialization
+        // to TOML fails with ValueAfterTable unless this gets reordered first.
+        let value = json!({"window": {"width": 800, "height": 600}, "volume": 5});
+
+        let data = Format::Toml.serialize(&value).unwrap();
+
+        assert_eq!(Format::Toml.parse(&data).unwrap(), value);
+    }
+
+    #[test]
+    fn toml_serialize_rejects_null() {
+        let value = json!({"volume": null});
+
+        assert!(matches!(
+            Format::Toml.serialize(&value),
+            Err(crate::Error::Type(_))
+        ));
+    }
+}
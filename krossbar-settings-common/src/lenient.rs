@@ -0,0 +1,160 @@
+//! Tolerant JSON reading for hand-edited settings files: strips `//`/`/* */` comments
+//! and trailing commas before handing the bytes to `serde_json`.
+
+/// Tolerate hand-edited quirks: strip comments first, then trailing commas, so the two
+/// compose correctly (e.g. a trailing comma followed by a comment before the closing brace)
+pub(crate) fn strip_comments_and_trailing_commas(data: &[u8]) -> Vec<u8> {
+    strip_trailing_commas(&strip_comments(data))
+}
+
+/// Strip `//` and `/* */` comments from **data**, outside of string literals
+fn strip_comments(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut i = 0;
+
+    while i < data.len() {
+        let byte = data[i];
+
+        if in_string {
+            out.push(byte);
+
+            if escaped {
+                escaped = false;
+            } else if byte == b'\\' {
+                escaped = true;
+            } else if byte == b'"' {
+                in_string = false;
+            }
+
+            i += 1;
+            continue;
+        }
+
+        match byte {
+            b'"' => {
+                in_string = true;
+                out.push(byte);
+                i += 1;
+            }
+            b'/' if data.get(i + 1) == Some(&b'/') => {
+                i += 2;
+                while i < data.len() && data[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if data.get(i + 1) == Some(&b'*') => {
+                i += 2;
+                while i + 1 < data.len() && !(data[i] == b'*' && data[i + 1] == b'/') {
+                    i += 1;
+                }
+                i = (i + 2).min(data.len());
+            }
+            _ => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    out
+}
+
+/// Strip commas that are only followed by whitespace and a closing `}`/`]`, outside of
+/// string literals. Expects comments to have already been stripped.
+fn strip_trailing_commas(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut i = 0;
+
+    while i < data.len() {
+        let byte = data[i];
+
+        if in_string {
+            out.push(byte);
+
+            if escaped {
+                escaped = false;
+            } else if byte == b'\\' {
+                escaped = true;
+            } else if byte == b'"' {
+                in_string = false;
+            }
+
+            i += 1;
+            continue;
+        }
+
+        match byte {
+            b'"' => {
+                in_string = true;
+                out.push(byte);
+                i += 1;
+            }
+            b',' => {
+                let mut lookahead = i + 1;
+                while lookahead < data.len() && (data[lookahead] as char).is_whitespace() {
+                    lookahead += 1;
+                }
+
+                if !matches!(data.get(lookahead), Some(b'}') | Some(b']')) {
+                    out.push(byte);
+                }
+
+                i += 1;
+            }
+            _ => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::strip_comments_and_trailing_commas;
+
+    fn stripped(input: &str) -> String {
+        String::from_utf8(strip_comments_and_trailing_commas(input.as_bytes())).unwrap()
+    }
+
+    #[test]
+    fn strips_line_comments() {
+        let parsed: serde_json::Value =
+            serde_json::from_str(&stripped("{\n  \"a\": 1 // comment\n}")).unwrap();
+        assert_eq!(parsed, serde_json::json!({"a": 1}));
+    }
+
+    #[test]
+    fn strips_block_comments() {
+        let parsed: serde_json::Value =
+            serde_json::from_str(&stripped("{ /* block */ \"a\": 1 }")).unwrap();
+        assert_eq!(parsed, serde_json::json!({"a": 1}));
+    }
+
+    #[test]
+    fn strips_trailing_commas() {
+        let parsed: serde_json::Value =
+            serde_json::from_str(&stripped("{\"a\": 1, \"b\": [1, 2,],}")).unwrap();
+        assert_eq!(parsed, serde_json::json!({"a": 1, "b": [1, 2]}));
+    }
+
+    #[test]
+    fn trailing_comma_followed_by_comment_still_strips() {
+        let parsed: serde_json::Value =
+            serde_json::from_str(&stripped("{\"a\": 1, // note\n}")).unwrap();
+        assert_eq!(parsed, serde_json::json!({"a": 1}));
+    }
+
+    #[test]
+    fn leaves_commas_and_slashes_inside_strings_alone() {
+        let parsed: serde_json::Value =
+            serde_json::from_str(&stripped(r#"{"a": "x, y // not a comment"}"#)).unwrap();
+        assert_eq!(parsed, serde_json::json!({"a": "x, y // not a comment"}));
+    }
+}
@@ -0,0 +1,11 @@
+mod backend;
+mod error;
+mod format;
+mod lenient;
+mod settings;
+mod watch;
+
+pub use backend::{FileBackend, LayeredBackend, MemoryBackend, SettingsBackend, SqliteBackend};
+pub use error::{Error, Result};
+pub use settings::Settings;
+pub use watch::ChangeEvent;
@@ -1,78 +1,112 @@
-use std::{
-    fs::{File, OpenOptions},
-    io::{Read, Seek, Write},
-    path::Path,
-};
+use std::path::{Path, PathBuf};
 
 use serde::{de::DeserializeOwned, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
+
+use crate::backend::{FileBackend, LayeredBackend, SettingsBackend};
 
 /// Settings handle
 pub struct Settings {
-    /// Settings file handle
-    settings_file: File,
+    /// Storage backend the settings are loaded from and persisted to
+    backend: Box<dyn SettingsBackend>,
 }
 
 impl Settings {
-    /// Open settings file at **path**
+    /// Open settings file at **path**, using the default file backend. The on-disk format
+    /// (JSON, RON, YAML or TOML) is chosen from the path's extension
     pub fn open(path: &Path) -> crate::Result<Self> {
-        // No settings fiel. Let's create and init one
-        let settings_file = if !Path::new(path).exists() {
-            let mut file =
-                File::create_new(path).map_err(|e| crate::Error::IoError(e.to_string()))?;
-
-            file.write_all("{}".as_bytes())
-                .map_err(|e| crate::Error::IoError(e.to_string()))?;
-
-            file
-        // Existing settings file
-        } else {
-            OpenOptions::new()
-                .read(true)
-                .write(true)
-                .open(path)
-                .map_err(|e| crate::Error::IoError(e.to_string()))?
-        };
+        Ok(Self::with_backend(FileBackend::new(path)?))
+    }
+
+    /// Create a settings handle backed by a custom [`SettingsBackend`], e.g. an in-memory
+    /// or SQLite-backed store instead of the default JSON file
+    pub fn with_backend(backend: impl SettingsBackend + 'static) -> Self {
+        Self {
+            backend: Box::new(backend),
+        }
+    }
 
-        Ok(Self { settings_file })
+    /// Open settings file at **path** like [`Settings::open`], but tolerate hand-edited
+    /// quirks: C-style `//`/`/* */` comments and trailing commas. Comments are dropped
+    /// the next time the file is written back.
+    pub fn open_lenient(path: &Path) -> crate::Result<Self> {
+        Ok(Self::with_backend(
+            FileBackend::new(path)?.with_lenient_parsing(),
+        ))
     }
 
-    /// Read a value from the settings file
+    /// Open a layered settings handle over **paths**, ordered from least to most
+    /// specific (e.g. a shipped defaults file followed by a user override file).
+    /// `get`/`list_values` see the deep-merged result, while `set`/`clear` only ever
+    /// touch the last (most specific) file.
+    pub fn open_layered(paths: &[PathBuf]) -> crate::Result<Self> {
+        Ok(Self::with_backend(LayeredBackend::new(paths)?))
+    }
+
+    /// Read a value from the settings file.
+    ///
+    /// **key** may be a plain top-level name (`"volume"`) or a `/`-separated
+    /// JSON Pointer-style path (`"window/size/width"`) addressing a nested value.
     pub fn get<T: DeserializeOwned>(&mut self, key: &str) -> crate::Result<T> {
         self.modify_settings(false, |map| {
-            if let Some(settings_value) = map.remove(key) {
-                serde_json::from_value(settings_value)
-                    .map_err(|e| crate::Error::Type(e.to_string()))
+            let settings_value = if is_nested_key(key) {
+                pointer_get(map, key)?
             } else {
-                Err(crate::Error::NotFound)
-            }
+                map.remove(key).ok_or(crate::Error::NotFound)?
+            };
+
+            serde_json::from_value(settings_value).map_err(|e| crate::Error::Type(e.to_string()))
         })
     }
 
-    /// Check if there's a value with a given **key**
+    /// Check if there's a value with a given **key**, which may be a nested path (see [`Settings::get`])
     pub fn has_value(&mut self, key: &str) -> crate::Result<bool> {
-        self.modify_settings(false, |map| Ok(map.contains_key(key)))
+        self.modify_settings(false, |map| {
+            if is_nested_key(key) {
+                Ok(pointer_get(map, key).is_ok())
+            } else {
+                Ok(map.contains_key(key))
+            }
+        })
     }
 
-    /// Write new value in the settings file
+    /// Write new value in the settings file under **key**, which may be a nested path
+    /// (see [`Settings::get`]). Missing intermediate objects along the path are created.
     pub fn set<T: Serialize>(&mut self, key: &str, value: &T) -> crate::Result<()> {
         self.modify_settings(true, |map| {
             let json_value =
                 serde_json::to_value(value).map_err(|e| crate::Error::Type(e.to_string()))?;
 
-            map.insert(key.to_owned(), json_value);
-            Ok(())
+            if is_nested_key(key) {
+                pointer_set(map, key, json_value)
+            } else {
+                map.insert(key.to_owned(), json_value);
+                Ok(())
+            }
         })
     }
 
-    /// Clear out entry with a given **key** from the file
+    /// Clear out entry with a given **key** from the file, which may be a nested path
+    /// (see [`Settings::get`])
     pub fn clear(&mut self, key: &str) -> crate::Result<()> {
         self.modify_settings(true, |map| {
-            map.remove(key);
-            Ok(())
+            if is_nested_key(key) {
+                pointer_clear(map, key)
+            } else {
+                map.remove(key);
+                Ok(())
+            }
         })
     }
 
+    /// Subscribe to changes made to the backing settings store, e.g. by another process
+    /// or a hand-edit of the file. Changes written through this handle are reported too.
+    /// Not every backend supports this; see
+    /// [`crate::SettingsBackend::watch`].
+    pub fn watch(&self) -> crate::Result<std::sync::mpsc::Receiver<crate::ChangeEvent>> {
+        self.backend.watch()
+    }
+
     /// List value in the settings file
     pub fn list_values(&mut self) -> crate::Result<Vec<(String, Value)>> {
         self.modify_settings(false, |map| {
@@ -93,51 +127,175 @@ impl Settings {
         write_back: bool,
         func: impl Fn(&mut serde_json::Map<String, Value>) -> crate::Result<T>,
     ) -> crate::Result<T> {
-        // Start reading from the beginning
-        self.settings_file
-            .seek(std::io::SeekFrom::Start(0))
-            .map_err(|e| crate::Error::IoError(e.to_string()))?;
-
-        let mut data = Vec::new();
-        // Read settings JSON data
-        self.settings_file
-            .read_to_end(&mut data)
-            .map_err(|e| crate::Error::IoError(e.to_string()))?;
-
-        // Convert to JSON
-        let json: Value =
-            serde_json::from_slice(&data).map_err(|e| crate::Error::Corrupted(e.to_string()))?;
-
-        if let Value::Object(mut map) = json {
-            let result = func(&mut map);
-
-            // If write back
-            if write_back && result.is_ok() {
-                // Start writing from the beggining of the file
-                self.settings_file
-                    .seek(std::io::SeekFrom::Start(0))
-                    .map_err(|e| crate::Error::IoError(e.to_string()))?;
-
-                // Truncate all the content
-                self.settings_file
-                    .set_len(0)
-                    .map_err(|e| crate::Error::IoError(e.to_string()))?;
-
-                // JSON to data
-                let data_to_write = serde_json::to_vec_pretty(&Value::Object(map))
-                    .map_err(|e| crate::Error::Type(e.to_string()))?;
-
-                // Write JSON
-                self.settings_file
-                    .write_all(&data_to_write)
-                    .map_err(|e| crate::Error::IoError(e.to_string()))?;
-            }
+        let mut map = self.backend.load()?;
 
-            result
-        } else {
-            Err(crate::Error::Corrupted(
-                "Root settings elemetn is not an Object".into(),
-            ))
+        let result = func(&mut map);
+
+        if write_back && result.is_ok() {
+            self.backend.store(&map)?;
         }
+
+        result
+    }
+}
+
+/// A key addresses a nested value if it contains a `/` separator
+fn is_nested_key(key: &str) -> bool {
+    key.contains('/')
+}
+
+/// Turn a bare `a/b/c` key into a proper RFC 6901 JSON Pointer (`/a/b/c`)
+fn to_pointer(key: &str) -> String {
+    if key.starts_with('/') {
+        key.to_owned()
+    } else {
+        format!("/{key}")
+    }
+}
+
+/// Split a pointer-style key into its segments, RFC 6901-unescaping each one (`~1` to
+/// `/`, then `~0` to `~`) so a literal `/` or `~` in a key round-trips the same way
+/// `Value::pointer_mut` resolves it on read
+fn pointer_segments(key: &str) -> Vec<String> {
+    key.split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+        .collect()
+}
+
+/// Read a value at a nested **key**, taking it out of the owning object along the way
+/// (a `null` is left in its place)
+fn pointer_get(map: &mut Map<String, Value>, key: &str) -> crate::Result<Value> {
+    let mut root = Value::Object(std::mem::take(map));
+
+    let value = root.pointer_mut(&to_pointer(key)).map(Value::take);
+
+    if let Value::Object(restored) = root {
+        *map = restored;
+    }
+
+    value.ok_or(crate::Error::NotFound)
+}
+
+/// Write **value** at a nested **key**, auto-vivifying missing intermediate objects
+fn pointer_set(map: &mut Map<String, Value>, key: &str, value: Value) -> crate::Result<()> {
+    let segments = pointer_segments(key);
+    let (last, parents) = segments.split_last().ok_or(crate::Error::NotFound)?;
+
+    let mut current = map;
+    for segment in parents {
+        let entry = current
+            .entry(segment.clone())
+            .or_insert_with(|| Value::Object(Map::new()));
+
+        current = match entry {
+            Value::Object(inner) => inner,
+            _ => return Err(crate::Error::NotFound),
+        };
+    }
+
+    current.insert(last.clone(), value);
+    Ok(())
+}
+
+/// Remove the value at a nested **key**, pruning nothing but the leaf itself
+fn pointer_clear(map: &mut Map<String, Value>, key: &str) -> crate::Result<()> {
+    let segments = pointer_segments(key);
+    let (last, parents) = segments.split_last().ok_or(crate::Error::NotFound)?;
+
+    let mut current = map;
+    for segment in parents {
+        current = match current.get_mut(segment) {
+            Some(Value::Object(inner)) => inner,
+            _ => return Err(crate::Error::NotFound),
+        };
+    }
+
+    current.remove(last).map(|_| ()).ok_or(crate::Error::NotFound)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn map(value: serde_json::Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn set_then_get_nested_key() {
+        let mut settings = map(json!({}));
+
+        pointer_set(&mut settings, "window/size/width", json!(800)).unwrap();
+
+        assert_eq!(
+            pointer_get(&mut settings, "window/size/width").unwrap(),
+            json!(800)
+        );
+    }
+
+    #[test]
+    fn set_auto_vivifies_missing_parents() {
+        let mut settings = map(json!({}));
+
+        pointer_set(&mut settings, "a/b/c", json!(1)).unwrap();
+
+        assert_eq!(settings, map(json!({"a": {"b": {"c": 1}}})));
+    }
+
+    #[test]
+    fn get_on_non_object_mid_path_is_not_found() {
+        let mut settings = map(json!({"a": 1}));
+
+        assert!(matches!(
+            pointer_get(&mut settings, "a/b"),
+            Err(crate::Error::NotFound)
+        ));
+    }
+
+    #[test]
+    fn set_on_non_object_mid_path_is_not_found() {
+        let mut settings = map(json!({"a": 1}));
+
+        assert!(matches!(
+            pointer_set(&mut settings, "a/b", json!(2)),
+            Err(crate::Error::NotFound)
+        ));
+    }
+
+    #[test]
+    fn clear_removes_only_the_leaf() {
+        let mut settings = map(json!({"window": {"width": 800, "height": 600}}));
+
+        pointer_clear(&mut settings, "window/width").unwrap();
+
+        assert_eq!(settings, map(json!({"window": {"height": 600}})));
+    }
+
+    #[test]
+    fn clear_on_missing_path_is_not_found() {
+        let mut settings = map(json!({}));
+
+        assert!(matches!(
+            pointer_clear(&mut settings, "window/width"),
+            Err(crate::Error::NotFound)
+        ));
+    }
+
+    #[test]
+    fn segments_unescape_tilde_and_slash_like_json_pointer() {
+        assert_eq!(pointer_segments("a~0b"), vec!["a~b"]);
+        assert_eq!(pointer_segments("a~1b"), vec!["a/b"]);
+    }
+
+    #[test]
+    fn set_and_get_agree_on_escaped_segments() {
+        let mut settings = map(json!({}));
+
+        pointer_set(&mut settings, "a~0b", json!(1)).unwrap();
+
+        assert_eq!(pointer_get(&mut settings, "a~0b").unwrap(), json!(1));
     }
 }
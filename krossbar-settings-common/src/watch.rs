@@ -0,0 +1,240 @@
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+    sync::mpsc::{channel, Receiver},
+    time::Duration,
+};
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde_json::{Map, Value};
+
+use crate::{format::Format, lenient::strip_comments_and_trailing_commas};
+
+/// A single key's change observed on a watched settings file
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeEvent {
+    /// A key that wasn't present before now has a value
+    Added { key: String, value: Value },
+    /// A key's value changed
+    Changed {
+        key: String,
+        old_value: Value,
+        new_value: Value,
+    },
+    /// A key that used to have a value is gone
+    Removed { key: String, old_value: Value },
+}
+
+/// How long to wait after the first change notification before re-reading the file,
+/// so a burst of writes from the same save coalesces into one diff
+const DEBOUNCE: Duration = Duration::from_millis(50);
+
+/// Watch **path** for modifications, re-reading and diffing it against the
+/// last-known content on every stable change. A transient parse failure (e.g. reading
+/// mid-write) is ignored rather than surfaced, on the assumption the next write settles.
+/// **lenient** must match whatever the backend itself used to read the file, or a
+/// legitimately comment-laden edit looks like a parse failure and live reload never fires.
+///
+/// This watches **path**'s parent directory rather than the file itself, filtering
+/// events down to ones naming our file. A watch armed directly on the file is dropped by
+/// the kernel the moment that inode is replaced, which is exactly what happens on every
+/// atomic save (ours included, see [`crate::backend::FileBackend::store`]): a watch on
+/// the directory survives the rename and keeps seeing every subsequent save.
+pub(crate) fn watch_path(
+    path: PathBuf,
+    format: Format,
+    lenient: bool,
+) -> crate::Result<Receiver<ChangeEvent>> {
+    let mut last_known = read_map(&path, format, lenient).unwrap_or_default();
+
+    let watch_dir = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."));
+    let file_name = path.file_name().map(OsString::from);
+
+    let (tx, rx) = channel();
+    let (notify_tx, notify_rx) = channel();
+
+    let mut watcher = notify::recommended_watcher(move |event| {
+        let _ = notify_tx.send(event);
+    })
+    .map_err(|e| crate::Error::Backend(e.to_string()))?;
+
+    watcher
+        .watch(&watch_dir, RecursiveMode::NonRecursive)
+        .map_err(|e| crate::Error::Backend(e.to_string()))?;
+
+    std::thread::spawn(move || {
+        // Keep the watcher alive for as long as this thread runs
+        let _watcher: RecommendedWatcher = watcher;
+
+        loop {
+            let event: notify::Event = match notify_rx.recv() {
+                Ok(Ok(event)) => event,
+                Ok(Err(_)) => continue,
+                Err(_) => break,
+            };
+
+            let touches_our_file = event
+                .paths
+                .iter()
+                .any(|changed| changed.file_name() == file_name.as_deref());
+
+            if !touches_our_file {
+                continue;
+            }
+
+            // Debounce: let the rest of a multi-step save land before re-reading
+            std::thread::sleep(DEBOUNCE);
+            while notify_rx.try_recv().is_ok() {}
+
+            let new_map = match read_map(&path, format, lenient) {
+                Ok(map) => map,
+                Err(_) => continue,
+            };
+
+            for change in diff(&last_known, &new_map) {
+                if tx.send(change).is_err() {
+                    return;
+                }
+            }
+
+            last_known = new_map;
+        }
+    });
+
+    Ok(rx)
+}
+
+fn read_map(path: &Path, format: Format, lenient: bool) -> crate::Result<Map<String, Value>> {
+    let mut data = std::fs::read(path).map_err(|e| crate::Error::IoError(e.to_string()))?;
+
+    if lenient && format == Format::Json {
+        data = strip_comments_and_trailing_commas(&data);
+    }
+
+    match format.parse(&data)? {
+        Value::Object(map) => Ok(map),
+        _ => Err(crate::Error::Corrupted(
+            "Root settings elemetn is not an Object".into(),
+        )),
+    }
+}
+
+fn diff(old: &Map<String, Value>, new: &Map<String, Value>) -> Vec<ChangeEvent> {
+    let mut events = Vec::new();
+
+    for (key, new_value) in new {
+        match old.get(key) {
+            None => events.push(ChangeEvent::Added {
+                key: key.clone(),
+                value: new_value.clone(),
+            }),
+            Some(old_value) if old_value != new_value => events.push(ChangeEvent::Changed {
+                key: key.clone(),
+                old_value: old_value.clone(),
+                new_value: new_value.clone(),
+            }),
+            _ => {}
+        }
+    }
+
+    for (key, old_value) in old {
+        if !new.contains_key(key) {
+            events.push(ChangeEvent::Removed {
+                key: key.clone(),
+                old_value: old_value.clone(),
+            });
+        }
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn map(value: serde_json::Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn diff_reports_added_changed_and_removed_keys() {
+        let old = map(json!({"a": 1, "b": 2, "c": 3}));
+        let new = map(json!({"a": 1, "b": 20, "d": 4}));
+
+        let mut events = diff(&old, &new);
+        events.sort_by_key(|event| match event {
+            ChangeEvent::Added { key, .. }
+            | ChangeEvent::Changed { key, .. }
+            | ChangeEvent::Removed { key, .. } => key.clone(),
+        });
+
+        assert_eq!(
+            events,
+            vec![
+                ChangeEvent::Changed {
+                    key: "b".into(),
+                    old_value: json!(2),
+                    new_value: json!(20),
+                },
+                ChangeEvent::Removed {
+                    key: "c".into(),
+                    old_value: json!(3),
+                },
+                ChangeEvent::Added {
+                    key: "d".into(),
+                    value: json!(4),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_is_empty_for_identical_maps() {
+        let same = map(json!({"a": 1}));
+
+        assert!(diff(&same, &same).is_empty());
+    }
+
+    #[test]
+    fn watch_emits_a_change_event_across_an_atomic_rename_save() {
+        let path = std::env::temp_dir().join(format!(
+            "krossbar-settings-watch-test-{}.json",
+            std::process::id()
+        ));
+        std::fs::write(&path, br#"{"a": 1}"#).unwrap();
+
+        let rx = watch_path(path.clone(), Format::Json, false).unwrap();
+
+        // Mirror FileBackend::store's own atomic-save pattern: write to a sibling temp
+        // file, then rename it over the original. This is exactly the pattern that a
+        // watch armed on the file itself (rather than its parent directory) misses.
+        let temp_path = path.with_file_name(format!(
+            ".{}.tmp",
+            path.file_name().unwrap().to_str().unwrap()
+        ));
+        std::fs::write(&temp_path, br#"{"a": 2}"#).unwrap();
+        std::fs::rename(&temp_path, &path).unwrap();
+
+        let event = rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("expected a change event after the atomic-rename save");
+
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(
+            event,
+            ChangeEvent::Changed {
+                key: "a".into(),
+                old_value: json!(1),
+                new_value: json!(2),
+            }
+        );
+    }
+}